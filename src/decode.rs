@@ -0,0 +1,231 @@
+use parse::Value;
+
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    MissingField(String),
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match *value {
+        Value::String(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "float",
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::True | Value::False => "boolean",
+        Value::Null => "null",
+    }
+}
+
+/// Walks a parsed `Value` tree to populate a user type, mirroring the
+/// `Decoder`/`Decodable` split from the old Rust `libserialize::json`.
+pub struct Decoder<'a> {
+    value: &'a Value,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(value: &'a Value) -> Decoder<'a> {
+        Decoder { value }
+    }
+
+    /// Look up `name` in the current object and decode it with `f`.
+    pub fn read_struct_field<T, F>(&self, name: &str, f: F) -> Result<T, DecodeError>
+    where
+        F: FnOnce(&Decoder) -> Result<T, DecodeError>,
+    {
+        match *self.value {
+            Value::Object(ref obj) => obj
+                .members
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| f(&Decoder::new(value)))
+                .unwrap_or_else(|| Err(DecodeError::MissingField(name.to_string()))),
+            ref other => Err(DecodeError::TypeMismatch {
+                expected: "object",
+                found: type_name(other),
+            }),
+        }
+    }
+}
+
+pub trait Decodable: Sized {
+    fn decode(decoder: &Decoder) -> Result<Self, DecodeError>;
+}
+
+impl Decodable for i32 {
+    fn decode(decoder: &Decoder) -> Result<i32, DecodeError> {
+        match *decoder.value {
+            Value::Integer(n) => Ok(n as i32),
+            ref other => Err(DecodeError::TypeMismatch {
+                expected: "integer",
+                found: type_name(other),
+            }),
+        }
+    }
+}
+
+impl Decodable for f64 {
+    fn decode(decoder: &Decoder) -> Result<f64, DecodeError> {
+        match *decoder.value {
+            Value::Float(n) => Ok(n),
+            Value::Integer(n) => Ok(n as f64),
+            ref other => Err(DecodeError::TypeMismatch {
+                expected: "float",
+                found: type_name(other),
+            }),
+        }
+    }
+}
+
+impl Decodable for bool {
+    fn decode(decoder: &Decoder) -> Result<bool, DecodeError> {
+        match *decoder.value {
+            Value::True => Ok(true),
+            Value::False => Ok(false),
+            ref other => Err(DecodeError::TypeMismatch {
+                expected: "boolean",
+                found: type_name(other),
+            }),
+        }
+    }
+}
+
+impl Decodable for String {
+    fn decode(decoder: &Decoder) -> Result<String, DecodeError> {
+        match *decoder.value {
+            Value::String(ref s) => Ok(s.clone()),
+            ref other => Err(DecodeError::TypeMismatch {
+                expected: "string",
+                found: type_name(other),
+            }),
+        }
+    }
+}
+
+impl<T: Decodable> Decodable for Option<T> {
+    fn decode(decoder: &Decoder) -> Result<Option<T>, DecodeError> {
+        match *decoder.value {
+            Value::Null => Ok(None),
+            _ => T::decode(decoder).map(Some),
+        }
+    }
+}
+
+impl<T: Decodable> Decodable for Vec<T> {
+    fn decode(decoder: &Decoder) -> Result<Vec<T>, DecodeError> {
+        match *decoder.value {
+            Value::Array(ref arr) => arr
+                .elements
+                .iter()
+                .map(|value| T::decode(&Decoder::new(value)))
+                .collect(),
+            ref other => Err(DecodeError::TypeMismatch {
+                expected: "array",
+                found: type_name(other),
+            }),
+        }
+    }
+}
+
+/// Decode `value` into any `Decodable` type, e.g. `from_value::<i32>(&value)`.
+pub fn from_value<T: Decodable>(value: &Value) -> Result<T, DecodeError> {
+    T::decode(&Decoder::new(value))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lex::{Lexer, Token};
+    use parse::parse_object;
+
+    fn parse(json: &str) -> Value {
+        let mut lexer = Lexer::new(json);
+        let mut tokens = lexer.lex().unwrap();
+        tokens.retain(|t| t.node != Token::Whitespace);
+        Value::Object(parse_object(&mut tokens.iter().peekable()).unwrap())
+    }
+
+    #[test]
+    fn test_decode_i32() {
+        let value = Value::Integer(5);
+        assert_eq!(from_value::<i32>(&value), Ok(5));
+    }
+
+    #[test]
+    fn test_decode_f64() {
+        let value = Value::Float(1.5);
+        assert_eq!(from_value::<f64>(&value), Ok(1.5));
+    }
+
+    #[test]
+    fn test_decode_bool() {
+        assert_eq!(from_value::<bool>(&Value::True), Ok(true));
+    }
+
+    #[test]
+    fn test_decode_string() {
+        let value = Value::String("hi".to_string());
+        assert_eq!(from_value::<String>(&value), Ok("hi".to_string()));
+    }
+
+    #[test]
+    fn test_decode_option_some_and_none() {
+        assert_eq!(from_value::<Option<i32>>(&Value::Integer(5)), Ok(Some(5)));
+        assert_eq!(from_value::<Option<i32>>(&Value::Null), Ok(None));
+    }
+
+    #[test]
+    fn test_decode_vec() {
+        let value = parse(r#"{"v":[1,2,3]}"#);
+        let decoder = Decoder::new(&value);
+        let result: Result<Vec<i32>, DecodeError> =
+            decoder.read_struct_field("v", Decodable::decode);
+        assert_eq!(result, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_decode_type_mismatch() {
+        let value = Value::String("not a number".to_string());
+        assert_eq!(
+            from_value::<i32>(&value),
+            Err(DecodeError::TypeMismatch {
+                expected: "integer",
+                found: "string",
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_missing_field() {
+        let value = parse(r#"{"a":1}"#);
+        let decoder = Decoder::new(&value);
+        let result: Result<i32, DecodeError> =
+            decoder.read_struct_field("b", Decodable::decode);
+        assert_eq!(result, Err(DecodeError::MissingField("b".to_string())));
+    }
+
+    #[test]
+    fn test_decode_nested_struct() {
+        #[derive(Debug, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl Decodable for Point {
+            fn decode(decoder: &Decoder) -> Result<Point, DecodeError> {
+                Ok(Point {
+                    x: decoder.read_struct_field("x", Decodable::decode)?,
+                    y: decoder.read_struct_field("y", Decodable::decode)?,
+                })
+            }
+        }
+
+        let value = parse(r#"{"x":1,"y":2}"#);
+        assert_eq!(from_value::<Point>(&value), Ok(Point { x: 1, y: 2 }));
+    }
+}
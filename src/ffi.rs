@@ -0,0 +1,147 @@
+//! C FFI bindings for the parse-and-query pipeline, in the style of
+//! `jsonpath_lib`'s `ffi` module: C strings in, C strings out, opaque
+//! handles for the parsed tree, null on failure instead of a panic.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use lex::{Lexer, Token};
+use parse::{parse_object, Value};
+use path::select;
+use serialize::to_string;
+
+unsafe fn str_from_raw<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+fn parse_json(json: &str) -> Option<Value> {
+    let mut lexer = Lexer::new(json);
+    let mut tokens = lexer.lex().ok()?;
+    tokens.retain(|token| token.node != Token::Whitespace);
+    parse_object(&mut tokens.iter().peekable())
+        .ok()
+        .map(Value::Object)
+}
+
+/// Parse `json` into an opaque `Value` handle. Returns a null pointer if
+/// `json` is not valid UTF-8 or fails to parse as a JSON object.
+///
+/// # Safety
+/// `json` must be either null or a pointer to a valid, nul-terminated C
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn ffi_parse(json: *const c_char) -> *mut Value {
+    match str_from_raw(json).and_then(parse_json) {
+        Some(value) => Box::into_raw(Box::new(value)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Evaluate `path` against the value behind `handle`, returning every matched
+/// node re-serialized to compact JSON and wrapped in a single JSON array
+/// (`[]` when nothing matches), as an owned C string. Returns a null
+/// pointer if `handle` or `path` is null, `path` is not valid UTF-8, or the
+/// expression fails to evaluate.
+///
+/// # Safety
+/// `handle` must be null or a live pointer previously returned by
+/// `ffi_parse` and not yet passed to `ffi_free`. `path` must be either
+/// null or a pointer to a valid, nul-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn ffi_select(handle: *const Value, path: *const c_char) -> *mut c_char {
+    if handle.is_null() {
+        return ptr::null_mut();
+    }
+    let path = match str_from_raw(path) {
+        Some(path) => path,
+        None => return ptr::null_mut(),
+    };
+    let matches = match select(&*handle, path) {
+        Ok(matches) => matches,
+        Err(_) => return ptr::null_mut(),
+    };
+    let json = format!(
+        "[{}]",
+        matches
+            .iter()
+            .map(|value| to_string(value))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    match CString::new(json) {
+        Ok(string) => string.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Release a handle returned by `ffi_parse`.
+///
+/// # Safety
+/// `handle` must be null or a pointer previously returned by `ffi_parse`
+/// that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn ffi_free(handle: *mut Value) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Release a string returned by `ffi_select`.
+///
+/// # Safety
+/// `string` must be null or a pointer previously returned by `ffi_select`
+/// that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn ffi_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ffi_parse_and_select_roundtrip() {
+        unsafe {
+            let json = CString::new(r#"{"tags":["a","b"]}"#).unwrap();
+            let handle = ffi_parse(json.as_ptr());
+            assert!(!handle.is_null());
+
+            let path = CString::new("$.tags[*]").unwrap();
+            let result = ffi_select(handle, path.as_ptr());
+            assert!(!result.is_null());
+            assert_eq!(CStr::from_ptr(result).to_str().unwrap(), r#"["a","b"]"#);
+
+            ffi_free_string(result);
+            ffi_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_ffi_parse_invalid_json_returns_null() {
+        unsafe {
+            let json = CString::new("not json").unwrap();
+            assert!(ffi_parse(json.as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_ffi_select_invalid_path_returns_null() {
+        unsafe {
+            let json = CString::new("{}").unwrap();
+            let handle = ffi_parse(json.as_ptr());
+            assert!(!handle.is_null());
+
+            let path = CString::new("$[").unwrap();
+            assert!(ffi_select(handle, path.as_ptr()).is_null());
+
+            ffi_free(handle);
+        }
+    }
+}
@@ -1,6 +1,31 @@
+use std::fmt;
 use std::iter::Peekable;
 use std::str::Chars;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn start() -> Position {
+        Position { line: 1, column: 1 }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub pos: Position,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Token {
     ObjectStart,
@@ -13,166 +38,426 @@ pub enum Token {
     Comma,
     Colon,
     Whitespace,
-    Integer(i32),
+    Integer(i64),
     Float(f64),
     String(String),
     NoMoreTokens,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum LexError {
-    InvalidToken,
+    UnexpectedChar(char, Position),
+    UnterminatedString(Position),
+    MalformedNumber(Position),
+    InvalidLiteral(Position),
+    MalformedEscape(Position),
+    LoneSurrogate(Position),
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LexError::UnexpectedChar(ch, pos) => {
+                write!(f, "error at {}: unexpected character '{}'", pos, ch)
+            }
+            LexError::UnterminatedString(pos) => {
+                write!(f, "error at {}: unterminated string", pos)
+            }
+            LexError::MalformedNumber(pos) => write!(f, "error at {}: malformed number", pos),
+            LexError::InvalidLiteral(pos) => write!(f, "error at {}: invalid literal", pos),
+            LexError::MalformedEscape(pos) => write!(f, "error at {}: malformed escape", pos),
+            LexError::LoneSurrogate(pos) => {
+                write!(f, "error at {}: lone surrogate in unicode escape", pos)
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Lexer<'a> {
     pub chars: Peekable<Chars<'a>>,
+    pos: Position,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn lex(&mut self) -> Result<Vec<Token>, LexError> {
-        let mut tokens: Vec<Token> = vec![];
+    pub fn new(input: &'a str) -> Lexer<'a> {
+        Lexer {
+            chars: input.chars().peekable(),
+            pos: Position::start(),
+        }
+    }
+
+    pub fn lex(&mut self) -> Result<Vec<Spanned<Token>>, LexError> {
+        let mut tokens: Vec<Spanned<Token>> = vec![];
 
         loop {
-            match next(&mut self.chars) {
-                Ok(Token::NoMoreTokens) => break,
-                Ok(token) => tokens.push(token),
+            let pos = self.pos;
+            match self.next() {
+                Ok(Token::NoMoreTokens) => {
+                    tokens.push(Spanned {
+                        node: Token::NoMoreTokens,
+                        pos,
+                    });
+                    break;
+                }
+                Ok(token) => tokens.push(Spanned { node: token, pos }),
                 Err(err) => return Err(err),
             }
         }
 
         Ok(tokens)
     }
-}
 
-fn next(mut chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    if let Some(&ch) = chars.peek() {
-        match ch {
-            '{' => {
-                chars.next();
-                Ok(Token::ObjectStart)
-            }
-            '}' => {
-                chars.next();
-                Ok(Token::ObjectEnd)
-            }
-            '[' => {
-                chars.next();
-                Ok(Token::ArrayStart)
-            }
-            ']' => {
-                chars.next();
-                Ok(Token::ArrayEnd)
-            }
-            ',' => {
-                chars.next();
-                Ok(Token::Comma)
-            }
-            ':' => {
-                chars.next();
-                Ok(Token::Colon)
+    fn advance(&mut self) -> Option<char> {
+        let ch = self.chars.next();
+        if let Some(ch) = ch {
+            if ch == '\n' {
+                self.pos.line += 1;
+                self.pos.column = 1;
+            } else {
+                self.pos.column += 1;
             }
-            '0' => {
-                chars.next();
-                Ok(Token::Integer(0))
+        }
+        ch
+    }
+
+    fn next(&mut self) -> Result<Token, LexError> {
+        if let Some(&ch) = self.chars.peek() {
+            match ch {
+                '{' => {
+                    self.advance();
+                    Ok(Token::ObjectStart)
+                }
+                '}' => {
+                    self.advance();
+                    Ok(Token::ObjectEnd)
+                }
+                '[' => {
+                    self.advance();
+                    Ok(Token::ArrayStart)
+                }
+                ']' => {
+                    self.advance();
+                    Ok(Token::ArrayEnd)
+                }
+                ',' => {
+                    self.advance();
+                    Ok(Token::Comma)
+                }
+                ':' => {
+                    self.advance();
+                    Ok(Token::Colon)
+                }
+                '0'..='9' | '-' => self.lex_number(),
+                '"' => self.lex_string(),
+                't' => self.lex_true(),
+                'f' => self.lex_false(),
+                'n' => self.lex_null(),
+                '\n' | '\t' | '\r' | ' ' => {
+                    self.advance();
+                    Ok(Token::Whitespace)
+                }
+                _ => Err(LexError::UnexpectedChar(ch, self.pos)),
             }
-            '1'...'9' => lex_number(&mut chars),
-            '"' => lex_string(&mut chars),
-            't' => lex_true(&mut chars),
-            'f' => lex_false(&mut chars),
-            'n' => lex_null(&mut chars),
-            '\n' | '\t' | '\r' | ' ' => {
-                chars.next();
-                Ok(Token::Whitespace)
+        } else {
+            Ok(Token::NoMoreTokens)
+        }
+    }
+
+    fn lex_string(&mut self) -> Result<Token, LexError> {
+        let start = self.pos;
+        let mut string = String::new();
+
+        if self.advance() != Some('"') {
+            return Err(LexError::UnexpectedChar('"', start));
+        }
+
+        loop {
+            match self.advance() {
+                Some('"') => break,
+                Some('\\') => string.push(self.lex_escape(start)?),
+                Some(ch) => string.push(ch),
+                None => return Err(LexError::UnterminatedString(start)),
             }
-            _ => Err(LexError::InvalidToken),
         }
-    } else {
-        Ok(Token::NoMoreTokens)
+
+        Ok(Token::String(string))
     }
-}
 
-fn lex_string(chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    let mut string = String::new();
+    fn lex_escape(&mut self, start: Position) -> Result<char, LexError> {
+        match self.advance() {
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('/') => Ok('/'),
+            Some('b') => Ok('\u{8}'),
+            Some('f') => Ok('\u{c}'),
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('u') => self.lex_unicode_escape(start),
+            Some(_) => Err(LexError::MalformedEscape(start)),
+            None => Err(LexError::UnterminatedString(start)),
+        }
+    }
 
-    if chars.next() != Some('"') {
-        return Err(LexError::InvalidToken);
+    fn lex_hex4(&mut self, start: Position) -> Result<u32, LexError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            match self.advance().and_then(|ch| ch.to_digit(16)) {
+                Some(digit) => value = value * 16 + digit,
+                None => return Err(LexError::MalformedEscape(start)),
+            }
+        }
+        Ok(value)
     }
 
-    loop {
-        if let Some(ch) = chars.next() {
-            if ch == '"' {
-                break;
-            } else {
-                string.push(ch);
+    fn lex_unicode_escape(&mut self, start: Position) -> Result<char, LexError> {
+        let unit = self.lex_hex4(start)?;
+
+        if (0xD800..=0xDBFF).contains(&unit) {
+            if self.advance() != Some('\\') || self.advance() != Some('u') {
+                return Err(LexError::LoneSurrogate(start));
+            }
+            let low = self.lex_hex4(start)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(LexError::LoneSurrogate(start));
             }
+            let code = (unit - 0xD800) * 0x400 + (low - 0xDC00) + 0x10000;
+            char::from_u32(code).ok_or(LexError::LoneSurrogate(start))
+        } else if (0xDC00..=0xDFFF).contains(&unit) {
+            Err(LexError::LoneSurrogate(start))
         } else {
-            return Err(LexError::InvalidToken);
+            char::from_u32(unit).ok_or(LexError::MalformedEscape(start))
         }
     }
 
-    Ok(Token::String(string))
-}
-
-fn lex_true(chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    match (chars.next(), chars.next(), chars.next(), chars.next()) {
-        (Some('t'), Some('r'), Some('u'), Some('e')) => Ok(Token::True),
-        _ => Err(LexError::InvalidToken),
+    fn lex_true(&mut self) -> Result<Token, LexError> {
+        let start = self.pos;
+        match (self.advance(), self.advance(), self.advance(), self.advance()) {
+            (Some('t'), Some('r'), Some('u'), Some('e')) => Ok(Token::True),
+            _ => Err(LexError::InvalidLiteral(start)),
+        }
     }
-}
 
-fn lex_false(chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    match (
-        chars.next(),
-        chars.next(),
-        chars.next(),
-        chars.next(),
-        chars.next(),
-    ) {
-        (Some('f'), Some('a'), Some('l'), Some('s'), Some('e')) => Ok(Token::False),
-        _ => Err(LexError::InvalidToken),
+    fn lex_false(&mut self) -> Result<Token, LexError> {
+        let start = self.pos;
+        match (
+            self.advance(),
+            self.advance(),
+            self.advance(),
+            self.advance(),
+            self.advance(),
+        ) {
+            (Some('f'), Some('a'), Some('l'), Some('s'), Some('e')) => Ok(Token::False),
+            _ => Err(LexError::InvalidLiteral(start)),
+        }
     }
-}
 
-fn lex_null(chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    match (chars.next(), chars.next(), chars.next(), chars.next()) {
-        (Some('n'), Some('u'), Some('l'), Some('l')) => Ok(Token::Null),
-        _ => Err(LexError::InvalidToken),
+    fn lex_null(&mut self) -> Result<Token, LexError> {
+        let start = self.pos;
+        match (self.advance(), self.advance(), self.advance(), self.advance()) {
+            (Some('n'), Some('u'), Some('l'), Some('l')) => Ok(Token::Null),
+            _ => Err(LexError::InvalidLiteral(start)),
+        }
     }
-}
 
-fn lex_number(chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    if let Ok(Token::Integer(integer)) = lex_digits(chars) {
-        if let Some('.') = chars.peek() {
-            chars.next();
-            if let Ok(Token::Integer(decimal)) = lex_digits(chars) {
-                if let Ok(f) = [integer.to_string(), decimal.to_string()]
-                    .join(".")
-                    .parse::<f64>()
-                {
-                    Ok(Token::Float(f))
-                } else {
-                    Err(LexError::InvalidToken)
+    fn lex_number(&mut self) -> Result<Token, LexError> {
+        let start = self.pos;
+        let mut lexeme = String::new();
+
+        if let Some(&'-') = self.chars.peek() {
+            lexeme.push('-');
+            self.advance();
+        }
+
+        match self.chars.peek().cloned() {
+            Some('0') => {
+                lexeme.push('0');
+                self.advance();
+                if self.chars.peek().map(|c| c.is_ascii_digit()) == Some(true) {
+                    return Err(LexError::MalformedNumber(start));
                 }
-            } else {
-                Err(LexError::InvalidToken)
             }
+            Some(ch) if ch.is_ascii_digit() => {
+                self.lex_digits(&mut lexeme);
+            }
+            _ => return Err(LexError::MalformedNumber(start)),
+        }
+
+        let mut is_float = false;
+
+        if let Some(&'.') = self.chars.peek() {
+            is_float = true;
+            lexeme.push('.');
+            self.advance();
+            if self.lex_digits(&mut lexeme) == 0 {
+                return Err(LexError::MalformedNumber(start));
+            }
+        }
+
+        if let Some(&ch) = self.chars.peek() {
+            if ch == 'e' || ch == 'E' {
+                is_float = true;
+                lexeme.push(ch);
+                self.advance();
+                if let Some(&sign) = self.chars.peek() {
+                    if sign == '+' || sign == '-' {
+                        lexeme.push(sign);
+                        self.advance();
+                    }
+                }
+                if self.lex_digits(&mut lexeme) == 0 {
+                    return Err(LexError::MalformedNumber(start));
+                }
+            }
+        }
+
+        if is_float {
+            lexeme
+                .parse::<f64>()
+                .map(Token::Float)
+                .map_err(|_| LexError::MalformedNumber(start))
         } else {
-            Ok(Token::Integer(integer))
+            lexeme
+                .parse::<i64>()
+                .map(Token::Integer)
+                .map_err(|_| LexError::MalformedNumber(start))
         }
-    } else {
-        Err(LexError::InvalidToken)
+    }
+
+    fn lex_digits(&mut self, lexeme: &mut String) -> usize {
+        let mut count = 0;
+        while let Some(&ch) = self.chars.peek() {
+            if ch.is_ascii_digit() {
+                lexeme.push(ch);
+                self.advance();
+                count += 1;
+            } else {
+                break;
+            }
+        }
+        count
     }
 }
 
-fn lex_digits(chars: &mut Peekable<Chars>) -> Result<Token, LexError> {
-    if chars.peek().map(|c| c.is_digit(10)).is_none() {
-        return Err(LexError::InvalidToken);
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lex_one(input: &str) -> Result<Token, LexError> {
+        let mut lexer = Lexer::new(input);
+        lexer.next()
     }
-    let mut digits = 0;
-    while let Some(Some(digit)) = chars.peek().map(|c| c.to_digit(11)) {
-        digits = digits * 10 + digit;
-        chars.next();
+
+    #[test]
+    fn test_lex_string_escaped_quote() {
+        assert_eq!(lex_one(r#""a\"b""#), Ok(Token::String("a\"b".to_string())));
+    }
+
+    #[test]
+    fn test_lex_string_escaped_newline() {
+        assert_eq!(
+            lex_one(r#""line\nbreak""#),
+            Ok(Token::String("line\nbreak".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_lex_string_unicode_escape() {
+        assert_eq!(lex_one(concat!("\"\\", "u00e9\"")), Ok(Token::String("é".to_string())));
+    }
+
+    #[test]
+    fn test_lex_string_surrogate_pair() {
+        assert_eq!(
+            lex_one(r#""\ud83d\ude00""#),
+            Ok(Token::String("\u{1f600}".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_lex_string_lone_surrogate() {
+        assert_eq!(
+            lex_one(r#""\ud800""#),
+            Err(LexError::LoneSurrogate(Position { line: 1, column: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_lex_string_malformed_escape() {
+        assert_eq!(
+            lex_one(r#""\q""#),
+            Err(LexError::MalformedEscape(Position { line: 1, column: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_lex_string_unterminated() {
+        assert_eq!(
+            lex_one("\"abc"),
+            Err(LexError::UnterminatedString(Position { line: 1, column: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_lex_number_zero() {
+        assert_eq!(lex_one("0"), Ok(Token::Integer(0)));
+    }
+
+    #[test]
+    fn test_lex_number_negative() {
+        assert_eq!(lex_one("-42"), Ok(Token::Integer(-42)));
+    }
+
+    #[test]
+    fn test_lex_number_large_integer() {
+        assert_eq!(lex_one("9223372036854775807"), Ok(Token::Integer(i64::MAX)));
+    }
+
+    #[test]
+    fn test_lex_number_fraction() {
+        assert_eq!(lex_one("1.05"), Ok(Token::Float(1.05)));
+    }
+
+    #[test]
+    fn test_lex_number_exponent() {
+        assert_eq!(lex_one("1e10"), Ok(Token::Float(1e10)));
+    }
+
+    #[test]
+    fn test_lex_number_negative_exponent() {
+        assert_eq!(lex_one("2.5e-3"), Ok(Token::Float(2.5e-3)));
+    }
+
+    #[test]
+    fn test_lex_number_leading_zero_rejected() {
+        assert_eq!(
+            lex_one("01"),
+            Err(LexError::MalformedNumber(Position { line: 1, column: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_lex_number_trailing_dot_rejected() {
+        assert_eq!(
+            lex_one("1."),
+            Err(LexError::MalformedNumber(Position { line: 1, column: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_lex_number_leading_dot_rejected() {
+        assert_eq!(
+            lex_one(".5"),
+            Err(LexError::UnexpectedChar('.', Position { line: 1, column: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_lex_number_bare_exponent_rejected() {
+        assert_eq!(
+            lex_one("1e"),
+            Err(LexError::MalformedNumber(Position { line: 1, column: 1 }))
+        );
     }
-    Ok(Token::Integer(digits as i32))
 }
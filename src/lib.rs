@@ -0,0 +1,7 @@
+pub mod alias;
+pub mod decode;
+pub mod ffi;
+pub mod lex;
+pub mod parse;
+pub mod path;
+pub mod serialize;
@@ -1,28 +1,132 @@
-#![feature(option_filter)]
+extern crate json_parse;
+
+use std::env;
+use std::fs;
 use std::io::{self, Read};
+use std::process;
+
+use json_parse::lex::{Lexer, Token};
+use json_parse::parse::{parse_object, Value};
+use json_parse::path::select;
+use json_parse::serialize::{to_string, to_string_pretty};
 
-mod alias;
-mod lex;
-mod parse;
+enum Mode {
+    Tokens,
+    Ast,
+    Serialize,
+    SerializePretty,
+    Select(String),
+}
 
-use lex::Lexer;
-use lex::Token;
-use parse::parse_object;
+fn read_input(path: Option<&str>) -> String {
+    let result = match path {
+        Some(path) => fs::read_to_string(path).map_err(|err| err.to_string()),
+        None => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map(|_| buffer)
+                .map_err(|err| err.to_string())
+        }
+    };
+    match result {
+        Ok(buffer) => buffer,
+        Err(err) => {
+            println!("error reading input: {}", err);
+            process::exit(1);
+        }
+    }
+}
 
 fn main() {
-    let mut buffer = String::new();
-    if io::stdin().read_to_string(&mut buffer).is_ok() {
-        let mut lexer = Lexer {
-            chars: buffer.chars().peekable(),
-        };
-        match lexer.lex() {
-            Ok(mut tokens) => {
-                tokens.retain(|token| token != &Token::Whitespace);
-                println!("{:?}", parse_object(&mut tokens.iter().peekable()));
+    let mut mode = Mode::Ast;
+    let mut path = None;
+    let mut args = env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tokens" => mode = Mode::Tokens,
+            "--ast" => mode = Mode::Ast,
+            "--serialize" => mode = Mode::Serialize,
+            "--serialize-pretty" => mode = Mode::SerializePretty,
+            "--select" => {
+                let expr = args.next().unwrap_or_else(|| {
+                    println!("error: --select requires a JSONPath expression");
+                    process::exit(1);
+                });
+                mode = Mode::Select(expr);
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    let buffer = read_input(path.as_deref());
+    let mut lexer = Lexer::new(&buffer);
+    let tokens = match lexer.lex() {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            println!("{}", err);
+            process::exit(1);
+        }
+    };
+
+    match mode {
+        Mode::Tokens => {
+            for token in &tokens {
+                println!("{:?}", token.node);
+            }
+        }
+        Mode::Ast => {
+            let mut tokens = tokens;
+            tokens.retain(|token| token.node != Token::Whitespace);
+            match parse_object(&mut tokens.iter().peekable()) {
+                Ok(object) => println!("{:?}", object),
+                Err(err) => {
+                    println!("{}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Mode::Serialize | Mode::SerializePretty => {
+            let mut tokens = tokens;
+            tokens.retain(|token| token.node != Token::Whitespace);
+            match parse_object(&mut tokens.iter().peekable()) {
+                Ok(object) => {
+                    let value = Value::Object(object);
+                    let rendered = match mode {
+                        Mode::SerializePretty => to_string_pretty(&value, 2),
+                        _ => to_string(&value),
+                    };
+                    println!("{}", rendered);
+                }
+                Err(err) => {
+                    println!("{}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        Mode::Select(expr) => {
+            let mut tokens = tokens;
+            tokens.retain(|token| token.node != Token::Whitespace);
+            match parse_object(&mut tokens.iter().peekable()) {
+                Ok(object) => {
+                    let value = Value::Object(object);
+                    match select(&value, &expr) {
+                        Ok(matches) => {
+                            for matched in matches {
+                                println!("{:?}", matched);
+                            }
+                        }
+                        Err(err) => {
+                            println!("{:?}", err);
+                            process::exit(1);
+                        }
+                    }
+                }
+                Err(err) => {
+                    println!("{}", err);
+                    process::exit(1);
+                }
             }
-            Err(err) => println!("{:?}", err),
         }
-    } else {
-        panic!("Error reading input.");
     }
 }
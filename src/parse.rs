@@ -1,37 +1,25 @@
-use lex::Token;
+use lex::{Position, Spanned, Token};
+use std::fmt;
 use std::iter::Peekable;
 use std::slice::Iter;
 
-type Tokens<'a> = Peekable<Iter<'a, Token>>;
+type Tokens<'a> = Peekable<Iter<'a, Spanned<Token>>>;
 
 #[derive(Debug, PartialEq)]
-pub enum Object {
-    Empty,
-    Nonempty(Box<Members>),
+pub struct Object {
+    pub members: Vec<(String, Value)>,
 }
 
 #[derive(Debug, PartialEq)]
-pub enum Members {
-    Pair(String, Value),
-    Pairs(String, Value, Box<Members>),
-}
-
-#[derive(Debug, PartialEq)]
-pub enum Array {
-    Empty,
-    Nonempty(Box<Elements>),
-}
-
-#[derive(Debug, PartialEq)]
-pub enum Elements {
-    Single(Value),
-    Many(Value, Box<Elements>),
+pub struct Array {
+    pub elements: Vec<Value>,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Value {
     String(String),
-    Number(JSONNumber),
+    Integer(i64),
+    Float(f64),
     Object(Object),
     Array(Array),
     True,
@@ -39,124 +27,316 @@ pub enum Value {
     Null,
 }
 
-#[derive(Debug, PartialEq)]
-pub enum JSONNumber {
-    Integer(i32),
-    Float(f64),
+impl Value {
+    /// If this is an object, look up `key` among its members.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match *self {
+            Value::Object(ref obj) => obj
+                .members
+                .iter()
+                .find(|(member_key, _)| member_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// If this is an array, look up the element at `index`.
+    pub fn index(&self, index: usize) -> Option<&Value> {
+        match *self {
+            Value::Array(ref arr) => arr.elements.get(index),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum ParseError {
-    ExpectedToken,
+    ExpectedValue(Position),
+    ExpectedKey(Position),
+    ExpectedColon(Position),
+    ExpectedObjectEnd(Position),
+    ExpectedArrayEnd(Position),
+    TrailingComma(Position),
+    UnexpectedEof(Position),
 }
 
-pub fn parse_object(mut tokens: &mut Tokens) -> Result<Object, ParseError> {
-    if tokens
-        .next()
-        .filter(|t| **t == Token::ObjectStart)
-        .is_none()
-    {
-        return Err(ParseError::ExpectedToken);
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::ExpectedValue(pos) => write!(f, "error at {}: expected a value", pos),
+            ParseError::ExpectedKey(pos) => write!(f, "error at {}: expected a string key", pos),
+            ParseError::ExpectedColon(pos) => write!(f, "error at {}: expected ':'", pos),
+            ParseError::ExpectedObjectEnd(pos) => write!(f, "error at {}: expected ',' or '}}'", pos),
+            ParseError::ExpectedArrayEnd(pos) => write!(f, "error at {}: expected ',' or ']'", pos),
+            ParseError::TrailingComma(pos) => write!(f, "error at {}: trailing comma", pos),
+            ParseError::UnexpectedEof(pos) => write!(f, "error at {}: unexpected end of input", pos),
+        }
     }
+}
 
-    if tokens.peek().filter(|t| ***t == Token::ObjectEnd).is_some() {
-        tokens.next();
-        return Ok(Object::Empty);
+/// Build the appropriate `ParseError` for a failure at `pos`: if the
+/// offending token is the lexer's end-of-input marker, report
+/// `UnexpectedEof` instead of `expected`, since "expected a value, found
+/// end of file" is a more useful diagnostic than a generic mismatch.
+fn error_at(expected: fn(Position) -> ParseError, token: Option<&Token>, pos: Position) -> ParseError {
+    match token {
+        Some(&Token::NoMoreTokens) | None => ParseError::UnexpectedEof(pos),
+        _ => expected(pos),
     }
+}
 
-    parse_members(&mut tokens).and_then(|members| {
-        tokens
-            .next()
-            .filter(|t| **t == Token::ObjectEnd)
-            .map_or(Err(ParseError::ExpectedToken), |_| {
-                Ok(Object::Nonempty(Box::new(members)))
-            })
-    })
+fn current_pos(tokens: &mut Tokens) -> Position {
+    tokens
+        .peek()
+        .map(|t| t.pos)
+        .unwrap_or_else(Position::start)
 }
 
-fn parse_members(mut tokens: &mut Tokens) -> Result<Members, ParseError> {
-    parse_pair(&mut tokens).and_then(|(key, value)| {
-        if tokens.peek().filter(|t| ***t == Token::Comma).is_none() {
-            return Ok(Members::Pair(key, value));
-        }
-        tokens.next();
-        parse_members(&mut tokens).map(|members| Members::Pairs(key, value, Box::new(members)))
-    })
+enum ObjectState {
+    KeyOrEnd,
+    Key,
+    Colon(String),
+    Value(String),
+    CommaOrEnd,
+}
+
+enum ArrayState {
+    ValueOrEnd,
+    Value,
+    CommaOrEnd,
+}
+
+enum Frame {
+    Object {
+        members: Vec<(String, Value)>,
+        state: ObjectState,
+    },
+    Array {
+        elements: Vec<Value>,
+        state: ArrayState,
+    },
 }
 
-fn parse_pair(mut tokens: &mut Tokens) -> Result<(String, Value), ParseError> {
-    match (tokens.next(), tokens.next()) {
-        (Some(&Token::String(ref key)), Some(&Token::Colon)) => {
-            parse_value(&mut tokens).map(|value| (key.clone(), value))
+enum StartAction {
+    PushObject,
+    PushArray,
+    Scalar(Value),
+}
+
+fn start_value(tokens: &mut Tokens, pos: Position) -> Result<StartAction, ParseError> {
+    let token = tokens.next().map(|t| &t.node);
+    match token {
+        Some(&Token::ObjectStart) => Ok(StartAction::PushObject),
+        Some(&Token::ArrayStart) => Ok(StartAction::PushArray),
+        Some(Token::String(s)) => Ok(StartAction::Scalar(Value::String(s.clone()))),
+        Some(&Token::Integer(n)) => Ok(StartAction::Scalar(Value::Integer(n))),
+        Some(&Token::Float(n)) => Ok(StartAction::Scalar(Value::Float(n))),
+        Some(&Token::True) => Ok(StartAction::Scalar(Value::True)),
+        Some(&Token::False) => Ok(StartAction::Scalar(Value::False)),
+        Some(&Token::Null) => Ok(StartAction::Scalar(Value::Null)),
+        _ => Err(error_at(ParseError::ExpectedValue, token, pos)),
+    }
+}
+
+fn apply_start(action: StartAction, stack: &mut Vec<Frame>) -> Option<Value> {
+    match action {
+        StartAction::PushObject => {
+            stack.push(Frame::Object {
+                members: vec![],
+                state: ObjectState::KeyOrEnd,
+            });
+            None
+        }
+        StartAction::PushArray => {
+            stack.push(Frame::Array {
+                elements: vec![],
+                state: ArrayState::ValueOrEnd,
+            });
+            None
         }
-        _ => Err(ParseError::ExpectedToken),
+        StartAction::Scalar(value) => Some(value),
     }
 }
 
-fn parse_value(mut tokens: &mut Tokens) -> Result<Value, ParseError> {
-    if let Some(t) = tokens.peek().map(|t| *t) {
-        match *t {
-            Token::String(ref string) => {
-                tokens.next();
-                Ok(Value::String(string.clone()))
+/// Parse a single JSON value from `tokens`, iteratively: nesting is tracked
+/// with an explicit stack of in-progress objects/arrays rather than the
+/// call stack, so input depth is bounded only by available memory.
+fn parse_value(tokens: &mut Tokens) -> Result<Value, ParseError> {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut completed: Option<Value> = None;
+
+    loop {
+        if let Some(value) = completed.take() {
+            let pos = current_pos(tokens);
+            match stack.last_mut() {
+                None => return Ok(value),
+                Some(&mut Frame::Array {
+                    ref mut elements,
+                    ref mut state,
+                }) => {
+                    elements.push(value);
+                    *state = ArrayState::CommaOrEnd;
+                }
+                Some(&mut Frame::Object {
+                    ref mut members,
+                    ref mut state,
+                }) => {
+                    let key = match *state {
+                        ObjectState::Value(ref key) => key.clone(),
+                        _ => return Err(ParseError::ExpectedValue(pos)),
+                    };
+                    members.push((key, value));
+                    *state = ObjectState::CommaOrEnd;
+                }
+            }
+            continue;
+        }
+
+        let pos = current_pos(tokens);
+
+        enum Next {
+            Value,
+            ArrayValueOrEnd,
+            ArrayValue,
+            ObjectKeyOrEnd,
+            ObjectKey,
+            ObjectColon,
+            ObjectCommaOrEnd,
+            ArrayCommaOrEnd,
+        }
+
+        let next = match stack.last() {
+            None => Next::Value,
+            Some(Frame::Array { state, .. }) => match *state {
+                ArrayState::ValueOrEnd => Next::ArrayValueOrEnd,
+                ArrayState::Value => Next::ArrayValue,
+                ArrayState::CommaOrEnd => Next::ArrayCommaOrEnd,
+            },
+            Some(Frame::Object { state, .. }) => match *state {
+                ObjectState::KeyOrEnd => Next::ObjectKeyOrEnd,
+                ObjectState::Key => Next::ObjectKey,
+                ObjectState::Colon(_) => Next::ObjectColon,
+                ObjectState::Value(_) => Next::Value,
+                ObjectState::CommaOrEnd => Next::ObjectCommaOrEnd,
+            },
+        };
+
+        match next {
+            Next::Value => {
+                let action = start_value(tokens, pos)?;
+                completed = apply_start(action, &mut stack);
             }
-            Token::Integer(number) => {
-                tokens.next();
-                Ok(Value::Number(JSONNumber::Integer(number)))
+            Next::ArrayValueOrEnd => {
+                if let Some(&Token::ArrayEnd) = tokens.peek().map(|t| &t.node) {
+                    tokens.next();
+                    if let Some(Frame::Array { elements, .. }) = stack.pop() {
+                        completed = Some(Value::Array(Array { elements }));
+                    }
+                } else {
+                    let action = start_value(tokens, pos)?;
+                    completed = apply_start(action, &mut stack);
+                }
             }
-            Token::Float(number) => {
-                tokens.next();
-                Ok(Value::Number(JSONNumber::Float(number)))
+            Next::ArrayValue => {
+                if let Some(&Token::ArrayEnd) = tokens.peek().map(|t| &t.node) {
+                    return Err(ParseError::TrailingComma(pos));
+                }
+                let action = start_value(tokens, pos)?;
+                completed = apply_start(action, &mut stack);
             }
-            Token::True => {
-                tokens.next();
-                Ok(Value::True)
+            Next::ObjectKeyOrEnd => {
+                let token = tokens.next().map(|t| &t.node);
+                match token {
+                    Some(&Token::ObjectEnd) => {
+                        if let Some(Frame::Object { members, .. }) = stack.pop() {
+                            completed = Some(Value::Object(Object { members }));
+                        }
+                    }
+                    Some(Token::String(key)) => {
+                        if let Some(&mut Frame::Object { ref mut state, .. }) = stack.last_mut() {
+                            *state = ObjectState::Colon(key.clone());
+                        }
+                    }
+                    _ => return Err(error_at(ParseError::ExpectedKey, token, pos)),
+                }
             }
-            Token::False => {
-                tokens.next();
-                Ok(Value::False)
+            Next::ObjectKey => {
+                let token = tokens.next().map(|t| &t.node);
+                match token {
+                    Some(&Token::ObjectEnd) => return Err(ParseError::TrailingComma(pos)),
+                    Some(Token::String(key)) => {
+                        if let Some(&mut Frame::Object { ref mut state, .. }) = stack.last_mut() {
+                            *state = ObjectState::Colon(key.clone());
+                        }
+                    }
+                    _ => return Err(error_at(ParseError::ExpectedKey, token, pos)),
+                }
             }
-            Token::Null => {
-                tokens.next();
-                Ok(Value::Null)
+            Next::ObjectColon => {
+                let token = tokens.next().map(|t| &t.node);
+                match token {
+                    Some(&Token::Colon) => {
+                        if let Some(&mut Frame::Object { ref mut state, .. }) = stack.last_mut() {
+                            let key = match *state {
+                                ObjectState::Colon(ref key) => key.clone(),
+                                _ => unreachable!(),
+                            };
+                            *state = ObjectState::Value(key);
+                        }
+                    }
+                    _ => return Err(error_at(ParseError::ExpectedColon, token, pos)),
+                }
+            }
+            Next::ObjectCommaOrEnd => {
+                let token = tokens.next().map(|t| &t.node);
+                match token {
+                    Some(&Token::Comma) => {
+                        if let Some(&mut Frame::Object { ref mut state, .. }) = stack.last_mut() {
+                            *state = ObjectState::Key;
+                        }
+                    }
+                    Some(&Token::ObjectEnd) => {
+                        if let Some(Frame::Object { members, .. }) = stack.pop() {
+                            completed = Some(Value::Object(Object { members }));
+                        }
+                    }
+                    _ => return Err(error_at(ParseError::ExpectedObjectEnd, token, pos)),
+                }
+            }
+            Next::ArrayCommaOrEnd => {
+                let token = tokens.next().map(|t| &t.node);
+                match token {
+                    Some(&Token::Comma) => {
+                        if let Some(&mut Frame::Array { ref mut state, .. }) = stack.last_mut() {
+                            *state = ArrayState::Value;
+                        }
+                    }
+                    Some(&Token::ArrayEnd) => {
+                        if let Some(Frame::Array { elements, .. }) = stack.pop() {
+                            completed = Some(Value::Array(Array { elements }));
+                        }
+                    }
+                    _ => return Err(error_at(ParseError::ExpectedArrayEnd, token, pos)),
+                }
             }
-            Token::ObjectStart => parse_object(&mut tokens).map(Value::Object),
-            Token::ArrayStart => parse_array(&mut tokens).map(Value::Array),
-            _ => Err(ParseError::ExpectedToken),
         }
-    } else {
-        Err(ParseError::ExpectedToken)
     }
 }
 
-fn parse_array(mut tokens: &mut Tokens) -> Result<Array, ParseError> {
-    if tokens.next().filter(|t| **t == Token::ArrayStart).is_none() {
-        return Err(ParseError::ExpectedToken);
-    }
-
-    if tokens.peek().filter(|t| ***t == Token::ArrayEnd).is_some() {
-        tokens.next();
-        return Ok(Array::Empty);
+pub fn parse_object(tokens: &mut Tokens) -> Result<Object, ParseError> {
+    let pos = current_pos(tokens);
+    match parse_value(tokens)? {
+        Value::Object(obj) => Ok(obj),
+        _ => Err(ParseError::ExpectedValue(pos)),
     }
-
-    parse_elements(&mut tokens).and_then(|elements| {
-        tokens
-            .next()
-            .filter(|t| **t == Token::ArrayEnd)
-            .map_or(Err(ParseError::ExpectedToken), |_| {
-                Ok(Array::Nonempty(Box::new(elements)))
-            })
-    })
 }
 
-fn parse_elements(mut tokens: &mut Tokens) -> Result<Elements, ParseError> {
-    parse_value(&mut tokens).and_then(|value| {
-        if tokens.peek().filter(|t| ***t == Token::Comma).is_none() {
-            return Ok(Elements::Single(value));
-        }
-        tokens.next();
-        parse_elements(&mut tokens).map(|elements| Elements::Many(value, Box::new(elements)))
-    })
+pub fn parse_array(tokens: &mut Tokens) -> Result<Array, ParseError> {
+    let pos = current_pos(tokens);
+    match parse_value(tokens)? {
+        Value::Array(arr) => Ok(arr),
+        _ => Err(ParseError::ExpectedValue(pos)),
+    }
 }
 
 #[cfg(test)]
@@ -164,282 +344,429 @@ mod test {
     use super::*;
     use lex::Token;
 
+    fn spanned(tokens: Vec<Token>) -> Vec<Spanned<Token>> {
+        tokens
+            .into_iter()
+            .map(|node| Spanned {
+                node,
+                pos: Position::start(),
+            })
+            .collect()
+    }
+
     #[test]
     fn test_parse_value_string() {
-        let result = parse_value(&mut vec![Token::String("string".to_string())].iter().peekable());
+        let result = parse_value(
+            &mut spanned(vec![Token::String("string".to_string())])
+                .iter()
+                .peekable(),
+        );
         assert_eq!(result, Ok(Value::String("string".to_string())));
     }
 
     #[test]
     fn test_parse_value_number() {
-        let result = parse_value(&mut vec![Token::Integer(5)].iter().peekable());
-        assert_eq!(result, Ok(Value::Number(JSONNumber::Integer(5))));
+        let result = parse_value(&mut spanned(vec![Token::Integer(5)]).iter().peekable());
+        assert_eq!(result, Ok(Value::Integer(5)));
     }
 
     #[test]
     fn test_parse_value_true() {
-        let result = parse_value(&mut vec![Token::True].iter().peekable());
+        let result = parse_value(&mut spanned(vec![Token::True]).iter().peekable());
         assert_eq!(result, Ok(Value::True));
     }
 
     #[test]
     fn test_parse_value_false() {
-        let result = parse_value(&mut vec![Token::False].iter().peekable());
+        let result = parse_value(&mut spanned(vec![Token::False]).iter().peekable());
         assert_eq!(result, Ok(Value::False));
     }
 
     #[test]
     fn test_parse_value_null() {
-        let result = parse_value(&mut vec![Token::Null].iter().peekable());
+        let result = parse_value(&mut spanned(vec![Token::Null]).iter().peekable());
         assert_eq!(result, Ok(Value::Null));
     }
 
     #[test]
     fn test_parse_value_no_token() {
-        let result = parse_value(&mut vec![].iter().peekable());
-        assert_eq!(result, Err(ParseError::ExpectedToken));
+        let result = parse_value(&mut spanned(vec![]).iter().peekable());
+        assert_eq!(result, Err(ParseError::UnexpectedEof(Position::start())));
     }
 
     #[test]
     fn test_parse_value_invalid_token() {
-        let result = parse_value(&mut vec![Token::ObjectStart].iter().peekable());
-        assert_eq!(result, Err(ParseError::ExpectedToken));
+        let result = parse_value(&mut spanned(vec![Token::ObjectStart, Token::Comma]).iter().peekable());
+        assert_eq!(result, Err(ParseError::ExpectedKey(Position::start())));
+    }
+
+    #[test]
+    fn test_parse_object_trailing_comma_rejected() {
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::Integer(1),
+                Token::Comma,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
+        assert_eq!(result, Err(ParseError::TrailingComma(Position::start())));
+    }
+
+    #[test]
+    fn test_parse_array_trailing_comma_rejected() {
+        let result = parse_array(
+            &mut spanned(vec![
+                Token::ArrayStart,
+                Token::Integer(1),
+                Token::Comma,
+                Token::ArrayEnd,
+            ]).iter()
+                .peekable(),
+        );
+        assert_eq!(result, Err(ParseError::TrailingComma(Position::start())));
+    }
+
+    #[test]
+    fn test_parse_object_missing_colon() {
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Integer(1),
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
+        assert_eq!(result, Err(ParseError::ExpectedColon(Position::start())));
     }
 
     #[test]
     fn test_parse_object_empty() {
-        let result =
-            parse_object(&mut vec![Token::ObjectStart, Token::ObjectEnd].iter().peekable());
-        assert_eq!(result, Ok(Object::Empty));
+        let result = parse_object(
+            &mut spanned(vec![Token::ObjectStart, Token::ObjectEnd])
+                .iter()
+                .peekable(),
+        );
+        assert_eq!(result, Ok(Object { members: vec![] }));
     }
 
     #[test]
     fn test_parse_object_member_string() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::String("value".to_string()),
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::String("value".to_string()),
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::String("value".to_string())
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::String("value".to_string()))],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_members() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key1".to_string()),
-            Token::Colon,
-            Token::String("value1".to_string()),
-            Token::Comma,
-            Token::String("key2".to_string()),
-            Token::Colon,
-            Token::String("value2".to_string()),
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key1".to_string()),
+                Token::Colon,
+                Token::String("value1".to_string()),
+                Token::Comma,
+                Token::String("key2".to_string()),
+                Token::Colon,
+                Token::String("value2".to_string()),
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pairs(
-                "key1".to_string(),
-                Value::String("value1".to_string()),
-                Box::new(Members::Pair(
-                    "key2".to_string(),
-                    Value::String("value2".to_string())
-                ))
-            ))))
+            Ok(Object {
+                members: vec![
+                    ("key1".to_string(), Value::String("value1".to_string())),
+                    ("key2".to_string(), Value::String("value2".to_string())),
+                ],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_int() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::Integer(5),
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::Integer(5),
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Number(JSONNumber::Integer(5))
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::Integer(5))],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_float() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::Float(0.5),
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::Float(0.5),
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Number(JSONNumber::Float(0.5))
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::Float(0.5))],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_true() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::True,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::True,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::True
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::True)],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_false() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::False,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::False,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::False
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::False)],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_null() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::Null,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::Null,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Null
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::Null)],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_array_empty() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::ArrayStart,
-            Token::ArrayEnd,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::ArrayStart,
+                Token::ArrayEnd,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Array(Array::Empty)
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::Array(Array { elements: vec![] }))],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_array_element() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::ArrayStart,
-            Token::Integer(5),
-            Token::ArrayEnd,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::ArrayStart,
+                Token::Integer(5),
+                Token::ArrayEnd,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Array(Array::Nonempty(Box::new(Elements::Single(Value::Number(
-                    JSONNumber::Integer(5)
-                )))))
-            ))))
+            Ok(Object {
+                members: vec![(
+                    "key".to_string(),
+                    Value::Array(Array {
+                        elements: vec![Value::Integer(5)],
+                    })
+                )],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_array_elements() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::ArrayStart,
-            Token::Integer(5),
-            Token::Comma,
-            Token::String("elements".to_string()),
-            Token::ArrayEnd,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::ArrayStart,
+                Token::Integer(5),
+                Token::Comma,
+                Token::String("elements".to_string()),
+                Token::ArrayEnd,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Array(Array::Nonempty(Box::new(Elements::Many(
-                    Value::Number(JSONNumber::Integer(5)),
-                    Box::new(Elements::Single(Value::String("elements".to_string())))
-                ))))
-            ))))
+            Ok(Object {
+                members: vec![(
+                    "key".to_string(),
+                    Value::Array(Array {
+                        elements: vec![
+                            Value::Integer(5),
+                            Value::String("elements".to_string()),
+                        ],
+                    })
+                )],
+            })
         );
     }
 
     #[test]
     fn test_parse_object_member_object() {
-        let result = parse_object(&mut vec![
-            Token::ObjectStart,
-            Token::String("key".to_string()),
-            Token::Colon,
-            Token::ObjectStart,
-            Token::ObjectEnd,
-            Token::ObjectEnd,
-        ].iter()
-            .peekable());
+        let result = parse_object(
+            &mut spanned(vec![
+                Token::ObjectStart,
+                Token::String("key".to_string()),
+                Token::Colon,
+                Token::ObjectStart,
+                Token::ObjectEnd,
+                Token::ObjectEnd,
+            ]).iter()
+                .peekable(),
+        );
         assert_eq!(
             result,
-            Ok(Object::Nonempty(Box::new(Members::Pair(
-                "key".to_string(),
-                Value::Object(Object::Empty)
-            ))))
+            Ok(Object {
+                members: vec![("key".to_string(), Value::Object(Object { members: vec![] }))],
+            })
         );
     }
+
+    #[test]
+    fn test_parse_value_negative_integer() {
+        let result = parse_value(&mut spanned(vec![Token::Integer(-5)]).iter().peekable());
+        assert_eq!(result, Ok(Value::Integer(-5)));
+    }
+
+    #[test]
+    fn test_parse_value_negative_exponent_float() {
+        let result = parse_value(&mut spanned(vec![Token::Float(-1.5e-3)]).iter().peekable());
+        assert_eq!(result, Ok(Value::Float(-1.5e-3)));
+    }
+
+    #[test]
+    fn test_parse_value_integer_beyond_i32() {
+        let large = i64::from(i32::MAX) + 1;
+        let result = parse_value(&mut spanned(vec![Token::Integer(large)]).iter().peekable());
+        assert_eq!(result, Ok(Value::Integer(large)));
+    }
+
+    #[test]
+    fn test_parse_deeply_nested_array_does_not_overflow() {
+        let depth = 10_000;
+        let mut tokens = Vec::with_capacity(depth * 2);
+        for _ in 0..depth {
+            tokens.push(Token::ArrayStart);
+        }
+        for _ in 0..depth {
+            tokens.push(Token::ArrayEnd);
+        }
+        let result = parse_array(&mut spanned(tokens).iter().peekable());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_parse_wide_array_does_not_overflow() {
+        let width = 100_000;
+        let mut tokens = vec![Token::ArrayStart];
+        for i in 0..width {
+            if i > 0 {
+                tokens.push(Token::Comma);
+            }
+            tokens.push(Token::Integer(i as i64));
+        }
+        tokens.push(Token::ArrayEnd);
+        let result = parse_array(&mut spanned(tokens).iter().peekable());
+        assert_eq!(result.unwrap().elements.len(), width);
+    }
+
+    #[test]
+    fn test_value_get() {
+        let value = Value::Object(Object {
+            members: vec![("key".to_string(), Value::Integer(5))],
+        });
+        assert_eq!(value.get("key"), Some(&Value::Integer(5)));
+        assert_eq!(value.get("missing"), None);
+        assert_eq!(Value::Integer(5).get("key"), None);
+    }
+
+    #[test]
+    fn test_value_index() {
+        let value = Value::Array(Array {
+            elements: vec![Value::Integer(1), Value::Integer(2)],
+        });
+        assert_eq!(value.index(1), Some(&Value::Integer(2)));
+        assert_eq!(value.index(5), None);
+        assert_eq!(Value::Integer(5).index(0), None);
+    }
 }
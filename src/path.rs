@@ -0,0 +1,303 @@
+use parse::Value;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, PartialEq)]
+pub enum PathError {
+    UnexpectedChar(char),
+    UnexpectedEnd,
+    InvalidIndex(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Child(String),
+    Index(usize),
+    Slice(Option<usize>, Option<usize>),
+    Wildcard,
+    Descendant,
+}
+
+/// Evaluate a JSONPath-like expression against `value`, returning every
+/// matching node. Supports the full operator set: `$`, `.name`, `['name']`,
+/// `[n]`, `[start:end]`, `.*`/`[*]` and the recursive-descent `..` operator.
+pub fn select<'a>(value: &'a Value, path: &str) -> Result<Vec<&'a Value>, PathError> {
+    let steps = tokenize(path)?;
+    let mut current = vec![value];
+    for step in &steps {
+        current = expand(current, step);
+    }
+    Ok(current)
+}
+
+fn tokenize(path: &str) -> Result<Vec<Step>, PathError> {
+    let mut chars = path.chars().peekable();
+    let mut steps = vec![];
+
+    if chars.peek() == Some(&'$') {
+        chars.next();
+    }
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            '.' => {
+                chars.next();
+                if chars.peek() == Some(&'.') {
+                    chars.next();
+                    steps.push(Step::Descendant);
+                    if let Some(&ch) = chars.peek() {
+                        if ch != '.' && ch != '[' {
+                            steps.push(Step::Child(lex_ident(&mut chars)?));
+                        }
+                    }
+                    continue;
+                }
+                if chars.peek() == Some(&'*') {
+                    chars.next();
+                    steps.push(Step::Wildcard);
+                    continue;
+                }
+                steps.push(Step::Child(lex_ident(&mut chars)?));
+            }
+            '[' => {
+                chars.next();
+                steps.push(lex_bracket(&mut chars)?);
+            }
+            _ => return Err(PathError::UnexpectedChar(ch)),
+        }
+    }
+
+    Ok(steps)
+}
+
+fn lex_ident(chars: &mut Peekable<Chars>) -> Result<String, PathError> {
+    let mut ident = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ch == '.' || ch == '[' {
+            break;
+        }
+        ident.push(ch);
+        chars.next();
+    }
+    if ident.is_empty() {
+        return Err(PathError::UnexpectedEnd);
+    }
+    Ok(ident)
+}
+
+fn lex_bracket(chars: &mut Peekable<Chars>) -> Result<Step, PathError> {
+    match chars.peek().cloned() {
+        Some('*') => {
+            chars.next();
+            expect(chars, ']')?;
+            Ok(Step::Wildcard)
+        }
+        Some('\'') => {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some(ch) => name.push(ch),
+                    None => return Err(PathError::UnexpectedEnd),
+                }
+            }
+            expect(chars, ']')?;
+            Ok(Step::Child(name))
+        }
+        Some(':') => {
+            chars.next();
+            let end = lex_optional_index(chars)?;
+            expect(chars, ']')?;
+            Ok(Step::Slice(None, end))
+        }
+        Some(ch) if ch.is_ascii_digit() => {
+            let start = lex_optional_index(chars)?;
+            if chars.peek() == Some(&':') {
+                chars.next();
+                let end = lex_optional_index(chars)?;
+                expect(chars, ']')?;
+                return Ok(Step::Slice(start, end));
+            }
+            expect(chars, ']')?;
+            start.map(Step::Index).ok_or(PathError::UnexpectedEnd)
+        }
+        Some(ch) => Err(PathError::UnexpectedChar(ch)),
+        None => Err(PathError::UnexpectedEnd),
+    }
+}
+
+fn lex_optional_index(chars: &mut Peekable<Chars>) -> Result<Option<usize>, PathError> {
+    let mut digits = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    if digits.is_empty() {
+        return Ok(None);
+    }
+    digits
+        .parse::<usize>()
+        .map(Some)
+        .map_err(|_| PathError::InvalidIndex(digits))
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), PathError> {
+    match chars.next() {
+        Some(ch) if ch == expected => Ok(()),
+        Some(ch) => Err(PathError::UnexpectedChar(ch)),
+        None => Err(PathError::UnexpectedEnd),
+    }
+}
+
+fn expand<'a>(nodes: Vec<&'a Value>, step: &Step) -> Vec<&'a Value> {
+    match *step {
+        Step::Child(ref name) => nodes
+            .into_iter()
+            .flat_map(|node| match *node {
+                Value::Object(ref obj) => obj
+                    .members
+                    .iter()
+                    .filter(|(key, _)| key == name)
+                    .map(|(_, value)| value)
+                    .collect(),
+                _ => vec![],
+            })
+            .collect(),
+        Step::Index(index) => nodes
+            .into_iter()
+            .flat_map(|node| match *node {
+                Value::Array(ref arr) => arr.elements.get(index).into_iter().collect(),
+                _ => vec![],
+            })
+            .collect(),
+        Step::Slice(start, end) => nodes
+            .into_iter()
+            .flat_map(|node| match *node {
+                Value::Array(ref arr) => {
+                    let start = start.unwrap_or(0);
+                    let end = end.unwrap_or(arr.elements.len()).min(arr.elements.len());
+                    if start >= end {
+                        vec![]
+                    } else {
+                        arr.elements[start..end].iter().collect()
+                    }
+                }
+                _ => vec![],
+            })
+            .collect(),
+        Step::Wildcard => nodes
+            .into_iter()
+            .flat_map(|node| match *node {
+                Value::Object(ref obj) => obj.members.iter().map(|(_, value)| value).collect(),
+                Value::Array(ref arr) => arr.elements.iter().collect(),
+                _ => vec![],
+            })
+            .collect(),
+        Step::Descendant => {
+            let mut descendants = vec![];
+            for node in nodes {
+                collect_descendants(node, &mut descendants);
+            }
+            descendants
+        }
+    }
+}
+
+fn collect_descendants<'a>(node: &'a Value, acc: &mut Vec<&'a Value>) {
+    acc.push(node);
+    match *node {
+        Value::Object(ref obj) => {
+            for (_, value) in &obj.members {
+                collect_descendants(value, acc);
+            }
+        }
+        Value::Array(ref arr) => {
+            for value in &arr.elements {
+                collect_descendants(value, acc);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use parse::{Array, Object};
+
+    fn sample() -> Value {
+        Value::Object(Object {
+            members: vec![
+                ("name".to_string(), Value::String("crate".to_string())),
+                (
+                    "tags".to_string(),
+                    Value::Array(Array {
+                        elements: vec![
+                            Value::String("json".to_string()),
+                            Value::Integer(5),
+                        ],
+                    }),
+                ),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_select_root() {
+        let value = sample();
+        assert_eq!(select(&value, "$").unwrap(), vec![&value]);
+    }
+
+    #[test]
+    fn test_select_child() {
+        let value = sample();
+        let result = select(&value, "$.name").unwrap();
+        assert_eq!(result, vec![&Value::String("crate".to_string())]);
+    }
+
+    #[test]
+    fn test_select_bracket_child_and_index() {
+        let value = sample();
+        let result = select(&value, "$['tags'][1]").unwrap();
+        assert_eq!(result, vec![&Value::Integer(5)]);
+    }
+
+    #[test]
+    fn test_select_wildcard() {
+        let value = sample();
+        let result = select(&value, "$.tags[*]").unwrap();
+        assert_eq!(
+            result,
+            vec![
+                &Value::String("json".to_string()),
+                &Value::Integer(5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_slice() {
+        let value = sample();
+        let result = select(&value, "$.tags[0:1]").unwrap();
+        assert_eq!(result, vec![&Value::String("json".to_string())]);
+    }
+
+    #[test]
+    fn test_select_slice_open_ended() {
+        let value = sample();
+        let result = select(&value, "$.tags[1:]").unwrap();
+        assert_eq!(result, vec![&Value::Integer(5)]);
+    }
+
+    #[test]
+    fn test_select_descendant() {
+        let value = sample();
+        let result = select(&value, "$..name").unwrap();
+        assert_eq!(result, vec![&Value::String("crate".to_string())]);
+    }
+}
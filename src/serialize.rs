@@ -0,0 +1,186 @@
+use parse::{Array, Object, Value};
+
+/// Serialize `value` to compact JSON text with no extraneous whitespace.
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(value, &mut out);
+    out
+}
+
+/// Serialize `value` to JSON text, indenting nested objects/arrays by
+/// `indent` spaces per level and placing each member/element on its own line.
+pub fn to_string_pretty(value: &Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_value_pretty(value, indent, 0, &mut out);
+    out
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match *value {
+        Value::String(ref string) => write_string(string, out),
+        Value::Integer(n) => out.push_str(&n.to_string()),
+        Value::Float(f) => write_float(f, out),
+        Value::True => out.push_str("true"),
+        Value::False => out.push_str("false"),
+        Value::Null => out.push_str("null"),
+        Value::Object(ref obj) => write_object(obj, out),
+        Value::Array(ref arr) => write_array(arr, out),
+    }
+}
+
+/// Render a float so it re-parses as `Value::Float`, not `Value::Integer`.
+/// `f64::to_string` drops the decimal point for whole values (`2.0` ->
+/// `"2"`, `1e10` -> `"10000000000"`), so append `.0` when neither a `.`
+/// nor an exponent marker is already present.
+fn write_float(f: f64, out: &mut String) {
+    let rendered = f.to_string();
+    if rendered.contains('.') || rendered.contains('e') || rendered.contains('E') {
+        out.push_str(&rendered);
+    } else {
+        out.push_str(&rendered);
+        out.push_str(".0");
+    }
+}
+
+fn write_object(obj: &Object, out: &mut String) {
+    out.push('{');
+    for (i, (key, value)) in obj.members.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(key, out);
+        out.push(':');
+        write_value(value, out);
+    }
+    out.push('}');
+}
+
+fn write_array(arr: &Array, out: &mut String) {
+    out.push('[');
+    for (i, value) in arr.elements.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_value(value, out);
+    }
+    out.push(']');
+}
+
+fn write_string(string: &str, out: &mut String) {
+    out.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+fn write_value_pretty(value: &Value, indent: usize, depth: usize, out: &mut String) {
+    match *value {
+        Value::Object(ref obj) => write_object_pretty(obj, indent, depth, out),
+        Value::Array(ref arr) => write_array_pretty(arr, indent, depth, out),
+        _ => write_value(value, out),
+    }
+}
+
+fn write_object_pretty(obj: &Object, indent: usize, depth: usize, out: &mut String) {
+    if obj.members.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+    out.push_str("{\n");
+    let len = obj.members.len();
+    for (i, (key, value)) in obj.members.iter().enumerate() {
+        push_indent(out, indent, depth + 1);
+        write_string(key, out);
+        out.push_str(": ");
+        write_value_pretty(value, indent, depth + 1, out);
+        if i + 1 < len {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    push_indent(out, indent, depth);
+    out.push('}');
+}
+
+fn write_array_pretty(arr: &Array, indent: usize, depth: usize, out: &mut String) {
+    if arr.elements.is_empty() {
+        out.push_str("[]");
+        return;
+    }
+    out.push_str("[\n");
+    let len = arr.elements.len();
+    for (i, value) in arr.elements.iter().enumerate() {
+        push_indent(out, indent, depth + 1);
+        write_value_pretty(value, indent, depth + 1, out);
+        if i + 1 < len {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    push_indent(out, indent, depth);
+    out.push(']');
+}
+
+fn push_indent(out: &mut String, indent: usize, depth: usize) {
+    for _ in 0..(indent * depth) {
+        out.push(' ');
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lex::{Lexer, Token};
+    use parse::parse_object;
+
+    fn parse(json: &str) -> Value {
+        let mut lexer = Lexer::new(json);
+        let mut tokens = lexer.lex().unwrap();
+        tokens.retain(|t| t.node != Token::Whitespace);
+        Value::Object(parse_object(&mut tokens.iter().peekable()).unwrap())
+    }
+
+    #[test]
+    fn test_roundtrip_compact() {
+        let json = r#"{"a":1,"b":[true,false,null,"x"],"c":{},"d":1e10}"#;
+        let value = parse(json);
+        let serialized = to_string(&value);
+        assert_eq!(parse(&serialized), value);
+    }
+
+    #[test]
+    fn test_roundtrip_compact_is_byte_stable() {
+        let json = r#"{"a":1,"b":[true,false,null,"x"],"c":{},"d":2.0}"#;
+        let value = parse(json);
+        assert_eq!(to_string(&value), json);
+    }
+
+    #[test]
+    fn test_roundtrip_pretty() {
+        let json = r#"{"a":1,"b":[1,2]}"#;
+        let value = parse(json);
+        let serialized = to_string_pretty(&value, 2);
+        assert_eq!(parse(&serialized), value);
+    }
+
+    #[test]
+    fn test_to_string_escapes_control_chars() {
+        let value = Value::String("a\"\\\n\tb".to_string());
+        assert_eq!(to_string(&value), r#""a\"\\\n\tb""#);
+    }
+
+    #[test]
+    fn test_to_string_pretty_empty_containers() {
+        let value = parse(r#"{"a":{},"b":[]}"#);
+        assert_eq!(to_string_pretty(&value, 2), "{\n  \"a\": {},\n  \"b\": []\n}");
+    }
+}